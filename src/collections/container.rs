@@ -1,22 +1,21 @@
+use super::ValidationReport;
+
 #[derive(Debug, PartialOrd, PartialEq)]
 pub enum ContainerError {
     Full,
     NotFound,
     IndexOutOfBounds,
     QuantityInsufficient,
+    /// The item cannot be stored: its quantity is zero or above `MAX_STACK`,
+    /// or its identifier is too large.
+    InvalidItem,
 }
 
 pub type ContainerResult<T> = Result<T, ContainerError>;
 
-pub trait Container<T> {
-    /// Creates a new Container with given capacity.
-    /// # Example
-    /// ```
-    /// let inv = Inventory::new(5);
-    /// assert_eq!(inv.capacity(), 5);
-    /// ```
-    fn with_capacity(capacity: usize) -> Self;
-
+/// The read-only half of a container, for code that should only ever look
+/// at its contents (e.g. rendering or building packets).
+pub trait ContainerView<T> {
     /// Returns the capacity of a container.
     fn capacity(&self) -> usize;
 
@@ -26,14 +25,51 @@ pub trait Container<T> {
     /// Returns whether a container has a specific item or not.
     fn contains(&self, item: &T) -> bool;
 
+    /// Gets an item at a specific slot.
+    fn get_at(&self, slot: usize) -> ContainerResult<T>;
+
+    /// Iterates over the occupied slots of a container, yielding each slot
+    /// index along with the item it holds.
+    ///
+    /// # Example
+    /// ```
+    /// # use rs_lib::collections::{Container, ContainerView, Inventory, WithCapacity};
+    /// # use rs_lib::entity::Item;
+    /// let mut inv = Inventory::with_capacity(3);
+    /// let _ = inv.add_at(Item::new(10, 1), 2);
+    /// assert_eq!(inv.iter().collect::<Vec<_>>(), vec![(2, Item::new(10, 1))]);
+    /// ```
+    fn iter(&self) -> Box<dyn Iterator<Item = (usize, T)> + '_>;
+
+    /// Checks the internal consistency of a container: the item count
+    /// matches the occupied slots, and no slot holds a zero quantity or more
+    /// than `MAX_STACK` of an item.
+    fn validate(&self) -> ValidationReport;
+}
+
+/// Constructs a container. Kept apart from `Container` so that
+/// `Box<dyn Container<T>>` can be used.
+pub trait WithCapacity {
+    /// Creates a new Container with given capacity.
+    /// # Example
+    /// ```
+    /// # use rs_lib::collections::{ContainerView, Inventory, WithCapacity};
+    /// let inv = Inventory::with_capacity(5);
+    /// assert_eq!(inv.capacity(), 5);
+    /// ```
+    fn with_capacity(capacity: usize) -> Self;
+}
+
+pub trait Container<T>: ContainerView<T> {
     /// Adds a given item to an container.
     ///
     /// # Example
     /// ```
+    /// # use rs_lib::collections::{Container, ContainerError, Inventory, WithCapacity};
+    /// # use rs_lib::entity::Item;
     /// let mut inv = Inventory::with_capacity(1);
-    /// assert_eq!(inv.add(Item::new(10)), Ok(()));
-    /// assert_eq!(inv.add(Item::new(10)),
-    ///            Err(ContainerError::ContainerFull);
+    /// assert_eq!(inv.add(Item::new(10, 1)), Ok(()));
+    /// assert_eq!(inv.add(Item::new(10, 1)), Err(ContainerError::Full));
     /// ```
     fn add(&mut self, item: T) -> ContainerResult<()>;
 
@@ -44,18 +80,17 @@ pub trait Container<T> {
     ///
     /// # Example
     /// ```
+    /// # use rs_lib::collections::{Container, Inventory, WithCapacity};
+    /// # use rs_lib::entity::Item;
     /// let mut inv = Inventory::with_capacity(1);
-    /// assert_eq!(inv.add(Item::new(10)), Ok(()));
-    /// assert_eq!(inv.remove(Item::new(10)), Ok(()));
+    /// assert_eq!(inv.add(Item::new(10, 1)), Ok(()));
+    /// assert_eq!(inv.remove(&Item::new(10, 1)), Ok(()));
     /// ```
     fn remove(&mut self, item: &T) -> ContainerResult<()>;
 
     /// Removes an item from a container at a given slot.
     fn remove_at(&mut self, slot: usize) -> ContainerResult<()>;
 
-    /// Gets an item at a specific slot.
-    fn get_at(&self, slot: usize) -> ContainerResult<T>;
-
     /// Swaps the slots of two items. If either `slot_a` or `slot_b` do not
     /// contain an item, then the non-empty slot is swapped with an empty one.
     fn swap(&mut self, slot_a: usize, slot_b: usize) -> ContainerResult<()>;
@@ -0,0 +1,9 @@
+/// A change to a single slot of a container.
+///
+/// `old` and `new` are `None` when the slot was, or became, empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerEvent<T> {
+    pub slot: usize,
+    pub old: Option<T>,
+    pub new: Option<T>,
+}
@@ -1,33 +1,332 @@
-use super::{Container, ContainerError, ContainerResult};
-use crate::entity::Item;
+use super::{
+    Container, ContainerError, ContainerEvent, ContainerResult, ContainerView, ValidationIssue,
+    ValidationReport, WithCapacity,
+};
+use crate::entity::{Item, MAX_STACK};
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+
+/// An inventory slot packed into a `u64`, a third of the size of an
+/// `Option<Item>`. The high 32 bits hold the item identifier plus one and
+/// the low 32 bits hold its quantity, so zero is an empty slot.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct InventorySlot(u64);
+
+impl InventorySlot {
+    const EMPTY: InventorySlot = InventorySlot(0);
+
+    /// Packs an item, failing if its identifier does not fit or its
+    /// quantity is zero or above `MAX_STACK`. This is the single check every
+    /// item goes through before it is stored.
+    fn pack(item: &Item) -> ContainerResult<InventorySlot> {
+        let identifier = u32::try_from(item.identifier())
+            .ok()
+            .and_then(|identifier| identifier.checked_add(1));
+        let quantity = Some(item.quantity())
+            .filter(|quantity| (1..=MAX_STACK).contains(quantity))
+            .and_then(|quantity| u32::try_from(quantity).ok());
+
+        match (identifier, quantity) {
+            (Some(identifier), Some(quantity)) => Ok(InventorySlot(
+                u64::from(identifier) << 32 | u64::from(quantity),
+            )),
+            _ => Err(ContainerError::InvalidItem),
+        }
+    }
+
+    fn is_empty(self) -> bool {
+        self == InventorySlot::EMPTY
+    }
+
+    fn item(&self) -> Option<Item> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let identifier = (self.0 >> 32) as usize - 1;
+        let quantity = (self.0 & u64::from(u32::MAX)) as usize;
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
-enum InventorySlot {
-    Empty,
-    Item(Item),
+        Some(Item::new(identifier, quantity))
+    }
+}
+
+impl fmt::Debug for InventorySlot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.item() {
+            Some(item) => item.fmt(f),
+            None => f.write_str("Empty"),
+        }
+    }
+}
+
+fn get_slot(items: &[InventorySlot], slot: usize) -> ContainerResult<Item> {
+    match items.get(slot) {
+        Some(slot) => slot.item().ok_or(ContainerError::NotFound),
+        None => Err(ContainerError::IndexOutOfBounds),
+    }
+}
+
+fn iter_slots(items: &[InventorySlot]) -> Box<dyn Iterator<Item = (usize, Item)> + '_> {
+    Box::new(
+        items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| slot.item().map(|item| (index, item))),
+    )
+}
+
+fn validate_slots(items: &[InventorySlot], item_count: usize) -> ValidationReport {
+    let mut report = ValidationReport::new();
+    let mut occupied = 0;
+
+    for (index, slot) in items.iter().enumerate() {
+        if let Some(item) = slot.item() {
+            occupied += 1;
+
+            if item.quantity() == 0 {
+                report.push(ValidationIssue::ZeroQuantity { slot: index });
+            } else if item.quantity() > MAX_STACK {
+                report.push(ValidationIssue::OverStacked {
+                    slot: index,
+                    quantity: item.quantity(),
+                });
+            }
+        }
+    }
+
+    if occupied != item_count {
+        report.push(ValidationIssue::CountMismatch {
+            tracked: item_count,
+            occupied,
+        });
+    }
+
+    report
 }
 
 /// Provides a default implementation of a container.
-#[derive(Debug, Clone)]
 pub struct Inventory {
     capacity: usize,
     item_count: usize,
-    items: Vec<InventorySlot>,
+    items: Arc<Vec<InventorySlot>>,
+    events: Option<Sender<ContainerEvent<Item>>>,
 }
 
-impl Container<Item> for Inventory {
-    fn with_capacity(capacity: usize) -> Self {
-        let mut inv = Inventory {
-            capacity: capacity,
-            item_count: 0,
-            items: Vec::new(),
-        };
+impl Inventory {
+    /// Attaches a channel that receives a `ContainerEvent` for every slot
+    /// this inventory changes, replacing any channel attached before. The
+    /// channel is detached once its receiver is dropped.
+    pub fn attach_events(&mut self, sender: Sender<ContainerEvent<Item>>) {
+        self.events = Some(sender);
+    }
+
+    /// Detaches the event channel, if one is attached.
+    pub fn detach_events(&mut self) {
+        self.events = None;
+    }
+
+    /// Takes a snapshot of the inventory's current contents.
+    ///
+    /// Snapshots share their slots with the inventory, so taking one does not
+    /// copy any items. The first mutation made afterwards copies the slot
+    /// list once; the snapshot itself never changes.
+    pub fn snapshot(&self) -> InventorySnapshot {
+        InventorySnapshot {
+            capacity: self.capacity,
+            item_count: self.item_count,
+            items: Arc::clone(&self.items),
+        }
+    }
+
+    /// Shrinks an inventory down to `capacity` slots.
+    ///
+    /// Items held in the slots being cut off are moved, in order, into the
+    /// empty slots that remain. Items that still do not fit are returned.
+    /// If `capacity` is not smaller than the current capacity, nothing
+    /// happens.
+    pub fn shrink_to(&mut self, capacity: usize) -> Vec<Item> {
+        if capacity >= self.capacity {
+            return Vec::new();
+        }
+
+        let cut = Arc::make_mut(&mut self.items).split_off(capacity);
+        self.capacity = capacity;
+
+        let mut free = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.is_empty())
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>()
+            .into_iter();
+
+        let mut leftover = Vec::new();
+
+        for (offset, slot) in cut.into_iter().enumerate() {
+            if let Some(item) = slot.item() {
+                self.item_count -= 1;
+                self.emit(capacity + offset, Some(item.clone()), None);
+
+                if let Some(index) = free.next() {
+                    self.set_slot(index, slot);
+                } else {
+                    leftover.push(item);
+                }
+            }
+        }
+
+        self.debug_validate();
+        leftover
+    }
+
+    /// Shrinks an inventory down to `capacity` slots, moving items that no
+    /// longer fit into `overflow` (e.g. a player's bank).
+    ///
+    /// Items that `overflow` cannot take either are returned, so the caller
+    /// can decide what to do with them instead of losing them.
+    pub fn shrink_into<C: Container<Item> + ?Sized>(
+        &mut self,
+        capacity: usize,
+        overflow: &mut C,
+    ) -> Vec<Item> {
+        let mut leftover = Vec::new();
+
+        for item in self.shrink_to(capacity) {
+            if overflow.add(item.clone()).is_err() {
+                leftover.push(item);
+            }
+        }
+
+        leftover
+    }
+
+    /// Replaces the contents of a slot, keeping the item count in step and
+    /// notifying the attached event channel.
+    fn set_slot(&mut self, index: usize, slot: InventorySlot) {
+        if self.items[index] == slot {
+            return;
+        }
+
+        let old = std::mem::replace(&mut Arc::make_mut(&mut self.items)[index], slot);
+
+        match (old.is_empty(), slot.is_empty()) {
+            (true, false) => self.item_count += 1,
+            (false, true) => self.item_count -= 1,
+            _ => {}
+        }
+
+        self.emit(index, old.item(), slot.item());
+    }
+
+    fn emit(&mut self, slot: usize, old: Option<Item>, new: Option<Item>) {
+        if let Some(sender) = &self.events {
+            if sender.send(ContainerEvent { slot, old, new }).is_err() {
+                self.events = None;
+            }
+        }
+    }
+
+    /// Asserts the inventory invariants hold after a mutation. Only checked
+    /// in debug builds.
+    fn debug_validate(&self) {
+        debug_assert!(
+            self.validate().is_valid(),
+            "inventory invariants violated: {:?}",
+            self.validate()
+        );
+    }
+}
+
+/// Only the contents are shown; the event channel is left out.
+impl fmt::Debug for Inventory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Inventory")
+            .field("capacity", &self.capacity)
+            .field("item_count", &self.item_count)
+            .field("items", &self.items)
+            .finish()
+    }
+}
+
+/// Cloning an inventory copies its contents only; the clone has no event
+/// channel attached.
+impl Clone for Inventory {
+    fn clone(&self) -> Self {
+        Inventory {
+            capacity: self.capacity,
+            item_count: self.item_count,
+            items: Arc::clone(&self.items),
+            events: None,
+        }
+    }
+}
+
+/// An immutable, cheaply cloned copy of an inventory's contents, for
+/// persistence and diffing.
+#[derive(Debug, Clone)]
+pub struct InventorySnapshot {
+    capacity: usize,
+    item_count: usize,
+    items: Arc<Vec<InventorySlot>>,
+}
+
+impl InventorySnapshot {
+    /// Lists the slots that differ between `older` and this snapshot, as the
+    /// events that would turn `older` into this snapshot.
+    pub fn changes_since(&self, older: &InventorySnapshot) -> Vec<ContainerEvent<Item>> {
+        if Arc::ptr_eq(&self.items, &older.items) {
+            return Vec::new();
+        }
+
+        let slots = self.items.len().max(older.items.len());
+        let slot_item =
+            |items: &[InventorySlot], index: usize| items.get(index).and_then(InventorySlot::item);
+
+        (0..slots)
+            .filter_map(|slot| {
+                let old = slot_item(&older.items, slot);
+                let new = slot_item(&self.items, slot);
+
+                if old != new {
+                    Some(ContainerEvent { slot, old, new })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl ContainerView<Item> for InventorySnapshot {
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn count(&self) -> usize {
+        self.item_count
+    }
 
-        inv.items.resize(capacity, InventorySlot::Empty);
+    fn contains(&self, item: &Item) -> bool {
+        InventorySlot::pack(item).is_ok_and(|slot| self.items.contains(&slot))
+    }
 
-        inv
+    fn get_at(&self, slot: usize) -> ContainerResult<Item> {
+        get_slot(&self.items, slot)
     }
 
+    fn iter(&self) -> Box<dyn Iterator<Item = (usize, Item)> + '_> {
+        iter_slots(&self.items)
+    }
+
+    fn validate(&self) -> ValidationReport {
+        validate_slots(&self.items, self.item_count)
+    }
+}
+
+impl ContainerView<Item> for Inventory {
     fn capacity(&self) -> usize {
         self.capacity
     }
@@ -37,19 +336,50 @@ impl Container<Item> for Inventory {
     }
 
     fn contains(&self, item: &Item) -> bool {
-        self.items.contains(&InventorySlot::Item(item.clone()))
+        InventorySlot::pack(item).is_ok_and(|slot| self.items.contains(&slot))
+    }
+
+    fn get_at(&self, slot: usize) -> ContainerResult<Item> {
+        get_slot(&self.items, slot)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (usize, Item)> + '_> {
+        iter_slots(&self.items)
+    }
+
+    fn validate(&self) -> ValidationReport {
+        validate_slots(&self.items, self.item_count)
+    }
+}
+
+impl WithCapacity for Inventory {
+    fn with_capacity(capacity: usize) -> Self {
+        let mut items = Vec::new();
+
+        items.resize(capacity, InventorySlot::EMPTY);
+
+        Inventory {
+            capacity,
+            item_count: 0,
+            items: Arc::new(items),
+            events: None,
+        }
     }
+}
 
+impl Container<Item> for Inventory {
     fn add(&mut self, item: Item) -> ContainerResult<()> {
         // TODO check stackability
-        for slot in self.items.iter_mut() {
-            if *slot == InventorySlot::Empty {
-                *slot = InventorySlot::Item(item);
-                self.item_count += 1;
-                return Ok(());
-            }
+        let packed = InventorySlot::pack(&item)?;
+        let free = self.items.iter().position(|slot| slot.is_empty());
+
+        if let Some(index) = free {
+            self.set_slot(index, packed);
+            self.debug_validate();
+            Ok(())
+        } else {
+            Err(ContainerError::Full)
         }
-        Err(ContainerError::Full)
     }
 
     fn add_at(&mut self, item: Item, slot: usize) -> ContainerResult<()> {
@@ -57,14 +387,16 @@ impl Container<Item> for Inventory {
             return Err(ContainerError::IndexOutOfBounds);
         }
 
-        self.items[slot] = InventorySlot::Item(item);
-        self.item_count += 1;
+        let packed = InventorySlot::pack(&item)?;
+
+        self.set_slot(slot, packed);
+        self.debug_validate();
         Ok(())
     }
 
     fn remove(&mut self, item: &Item) -> ContainerResult<()> {
-        for slot in self.items.iter_mut() {
-            if let InventorySlot::Item(i) = slot {
+        for index in 0..self.capacity {
+            if let Some(i) = self.items[index].item() {
                 if i.identifier() == item.identifier() {
                     if i.quantity() > item.quantity() {
                         return Err(ContainerError::QuantityInsufficient);
@@ -73,12 +405,12 @@ impl Container<Item> for Inventory {
                     let difference = item.quantity() - i.quantity();
 
                     if difference == 0 {
-                        *slot = InventorySlot::Empty;
-                        self.item_count -= 1;
+                        self.set_slot(index, InventorySlot::EMPTY);
                     } else {
                         let new_item = Item::new(i.identifier(), difference);
-                        *slot = InventorySlot::Item(new_item);
+                        self.set_slot(index, InventorySlot::pack(&new_item)?);
                     }
+                    self.debug_validate();
                     return Ok(());
                 }
             }
@@ -91,40 +423,38 @@ impl Container<Item> for Inventory {
             return Err(ContainerError::IndexOutOfBounds);
         }
 
-        if let InventorySlot::Item(_) = self.items[slot] {
-            self.items[slot] = InventorySlot::Empty;
-            self.item_count -= 1;
+        if !self.items[slot].is_empty() {
+            self.set_slot(slot, InventorySlot::EMPTY);
+            self.debug_validate();
             Ok(())
         } else {
             Err(ContainerError::NotFound)
         }
     }
 
-    fn get_at(&self, slot: usize) -> ContainerResult<Item> {
-        if slot >= self.capacity {
-            return Err(ContainerError::IndexOutOfBounds);
-        }
-
-        if let InventorySlot::Item(item) = &self.items[slot] {
-            Ok(item.clone())
-        } else {
-            Err(ContainerError::NotFound)
-        }
-    }
-
     fn swap(&mut self, slot_a: usize, slot_b: usize) -> ContainerResult<()> {
         if slot_a >= self.capacity || slot_b >= self.capacity {
             return Err(ContainerError::IndexOutOfBounds);
         }
 
-        self.items.swap(slot_a, slot_b);
+        let a = self.items[slot_a];
+        let b = self.items[slot_b];
+
+        self.set_slot(slot_a, b);
+        self.set_slot(slot_b, a);
+        self.debug_validate();
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod inventory_tests {
-    use super::{Container, ContainerError, Inventory, Item};
+    use super::{
+        Container, ContainerError, ContainerEvent, ContainerView, Inventory, InventorySlot, Item,
+        ValidationIssue, WithCapacity, MAX_STACK,
+    };
+    use std::sync::mpsc;
+    use std::sync::Arc;
 
     #[test]
     fn inv_capacity_and_add() {
@@ -188,4 +518,228 @@ mod inventory_tests {
 
         assert_eq!(inv.swap(0, 50), Err(ContainerError::IndexOutOfBounds));
     }
+
+    #[test]
+    fn inv_events() {
+        let (sender, receiver) = mpsc::channel();
+        let mut inv = Inventory::with_capacity(2);
+
+        inv.attach_events(sender);
+
+        let _ = inv.add(Item::new(5, 1));
+        let _ = inv.swap(0, 1);
+
+        let events = receiver.try_iter().collect::<Vec<_>>();
+
+        assert_eq!(
+            events,
+            vec![
+                ContainerEvent {
+                    slot: 0,
+                    old: None,
+                    new: Some(Item::new(5, 1))
+                },
+                ContainerEvent {
+                    slot: 0,
+                    old: Some(Item::new(5, 1)),
+                    new: None
+                },
+                ContainerEvent {
+                    slot: 1,
+                    old: None,
+                    new: Some(Item::new(5, 1))
+                },
+            ]
+        );
+
+        assert!(!format!("{:?}", inv).contains("events"));
+
+        // the channel is dropped once the receiver goes away
+        drop(receiver);
+        let _ = inv.remove_at(1);
+        assert!(inv.events.is_none());
+    }
+
+    #[test]
+    fn inv_snapshot() {
+        let mut inv = Inventory::with_capacity(3);
+
+        let _ = inv.add_at(Item::new(1, 1), 0);
+        let before = inv.snapshot();
+
+        let _ = inv.add_at(Item::new(2, 1), 2);
+        let _ = inv.remove_at(0);
+        let after = inv.snapshot();
+
+        // the older snapshot is untouched by later mutations
+        assert_eq!(before.get_at(0), Ok(Item::new(1, 1)));
+        assert_eq!(before.count(), 1);
+        assert!(before.changes_since(&before.clone()).is_empty());
+
+        assert_eq!(
+            after.changes_since(&before),
+            vec![
+                ContainerEvent {
+                    slot: 0,
+                    old: Some(Item::new(1, 1)),
+                    new: None
+                },
+                ContainerEvent {
+                    slot: 2,
+                    old: None,
+                    new: Some(Item::new(2, 1))
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn inv_dyn_container() {
+        let mut containers: Vec<Box<dyn Container<Item>>> = vec![
+            Box::new(Inventory::with_capacity(28)),
+            Box::new(Inventory::with_capacity(192)),
+        ];
+
+        for container in containers.iter_mut() {
+            assert_eq!(container.add(Item::new(1, 1)), Ok(()));
+        }
+
+        let mut overflow = Inventory::with_capacity(2);
+        let _ = overflow.add(Item::new(2, 1));
+        let _ = overflow.add(Item::new(3, 1));
+        let leftover = overflow.shrink_into(1, containers[1].as_mut());
+
+        assert!(leftover.is_empty());
+        assert_eq!(containers[1].count(), 2);
+    }
+
+    #[test]
+    fn inv_view() {
+        fn occupied(view: &dyn ContainerView<Item>) -> Vec<usize> {
+            view.iter().map(|(slot, _)| slot).collect()
+        }
+
+        let mut inv = Inventory::with_capacity(4);
+
+        let _ = inv.add_at(Item::new(7, 1), 1);
+        let _ = inv.add_at(Item::new(8, 1), 3);
+
+        assert_eq!(occupied(&inv), vec![1, 3]);
+    }
+
+    #[test]
+    fn inv_shrink_to() {
+        let mut inv = Inventory::with_capacity(5);
+
+        let _ = inv.add_at(Item::new(0, 1), 0);
+        let _ = inv.add_at(Item::new(3, 1), 3);
+        let _ = inv.add_at(Item::new(4, 1), 4);
+
+        let leftover = inv.shrink_to(2);
+
+        // item #3 takes the free 1st index, item #4 no longer fits
+        assert_eq!(inv.capacity(), 2);
+        assert_eq!(inv.count(), 2);
+        assert_eq!(inv.get_at(1).unwrap().identifier(), 3);
+        assert_eq!(leftover, vec![Item::new(4, 1)]);
+
+        // growing is not a shrink
+        assert_eq!(inv.shrink_to(10), vec![]);
+        assert_eq!(inv.capacity(), 2);
+    }
+
+    #[test]
+    fn inv_shrink_into() {
+        let mut inv = Inventory::with_capacity(3);
+        let mut bank = Inventory::with_capacity(1);
+
+        for i in 0..3 {
+            let _ = inv.add(Item::new(i, 1));
+        }
+
+        let leftover = inv.shrink_into(1, &mut bank);
+
+        assert_eq!(inv.count(), 1);
+        assert_eq!(bank.get_at(0).unwrap().identifier(), 1);
+        assert_eq!(leftover, vec![Item::new(2, 1)]);
+    }
+
+    #[test]
+    fn inv_add_at_occupied() {
+        let mut inv = Inventory::with_capacity(2);
+
+        let _ = inv.add_at(Item::new(0, 1), 0);
+        let _ = inv.add_at(Item::new(1, 1), 0);
+
+        // replacing an item does not count it twice
+        assert_eq!(inv.count(), 1);
+        assert!(inv.validate().is_valid());
+    }
+
+    #[test]
+    fn inv_packed_slots() {
+        let mut inv = Inventory::with_capacity(2);
+        let largest = Item::new(u32::MAX as usize - 1, MAX_STACK);
+
+        assert_eq!(std::mem::size_of::<InventorySlot>(), 8);
+
+        assert_eq!(inv.add_at(largest.clone(), 0), Ok(()));
+        assert_eq!(inv.get_at(0), Ok(largest));
+        assert_eq!(inv.add(Item::new(0, 1)), Ok(()));
+        assert_eq!(inv.get_at(1), Ok(Item::new(0, 1)));
+
+        assert_eq!(
+            inv.add_at(Item::new(u32::MAX as usize, 1), 0),
+            Err(ContainerError::InvalidItem)
+        );
+        assert_eq!(
+            inv.add_at(Item::new(1, u32::MAX as usize + 1), 0),
+            Err(ContainerError::InvalidItem)
+        );
+        assert!(!inv.contains(&Item::new(u32::MAX as usize, 1)));
+    }
+
+    #[test]
+    fn inv_add_invalid_quantity() {
+        let mut inv = Inventory::with_capacity(2);
+
+        for quantity in [0, MAX_STACK + 1].iter() {
+            let item = Item::new(1, *quantity);
+
+            assert_eq!(inv.add(item.clone()), Err(ContainerError::InvalidItem));
+            assert_eq!(inv.add_at(item, 0), Err(ContainerError::InvalidItem));
+            assert_eq!(inv.count(), 0);
+        }
+
+        assert!(inv.validate().is_valid());
+    }
+
+    #[test]
+    fn inv_validate() {
+        let mut inv = Inventory::with_capacity(3);
+
+        // bypass the debug assertions by writing the slots directly
+        let items = Arc::make_mut(&mut inv.items);
+        // raw slots, since pack refuses both of these
+        items[0] = InventorySlot(1 << 32);
+        items[2] = InventorySlot(3 << 32 | (MAX_STACK as u64 + 1));
+
+        let report = inv.validate();
+
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.issues(),
+            &[
+                ValidationIssue::ZeroQuantity { slot: 0 },
+                ValidationIssue::OverStacked {
+                    slot: 2,
+                    quantity: MAX_STACK + 1
+                },
+                ValidationIssue::CountMismatch {
+                    tracked: 0,
+                    occupied: 2
+                },
+            ]
+        );
+    }
 }
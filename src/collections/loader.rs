@@ -0,0 +1,213 @@
+use super::{Container, ContainerError, WithCapacity};
+use crate::entity::{DefinitionRegistry, Item};
+
+/// What to do with a saved item that does not match the definition registry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadPolicy {
+    /// Fail the whole load.
+    Reject,
+    /// Leave the slot empty.
+    Drop,
+    /// Replace the item with a single item of the given identifier, so the
+    /// slot is kept for later inspection. The identifier must be in the
+    /// registry.
+    Placeholder(usize),
+}
+
+/// A saved item that does not match the definition registry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadIssue {
+    /// No item is defined with this identifier.
+    UnknownItem { slot: usize, identifier: usize },
+    /// The quantity is zero or more than the item can stack to.
+    InvalidQuantity { slot: usize, quantity: usize },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LoadError {
+    /// The `LoadPolicy::Placeholder` identifier is not in the registry.
+    UnknownPlaceholder { identifier: usize },
+    /// More slots were saved than the container can hold.
+    TooManySlots { slots: usize, capacity: usize },
+    /// An item was rejected under `LoadPolicy::Reject`.
+    Rejected(LoadIssue),
+    /// The container refused an item that passed the checks.
+    Container { slot: usize, error: ContainerError },
+}
+
+/// Builds a container from saved slots, checking every item against
+/// `registry`.
+///
+/// Items that fail the check are handled according to `policy`. Along with
+/// the container, every issue found is returned so it can be logged.
+///
+/// # Example
+/// ```
+/// # use rs_lib::collections::{load, ContainerView, Inventory, LoadIssue, LoadPolicy};
+/// # use rs_lib::entity::{DefinitionRegistry, Item, ItemDefinition};
+/// let mut registry = DefinitionRegistry::new();
+/// registry.register(ItemDefinition::new(10, false));
+///
+/// let slots = vec![Some(Item::new(10, 1)), Some(Item::new(99, 1))];
+/// let (inv, issues) = load::<Inventory>(2, slots, &registry, LoadPolicy::Drop).unwrap();
+///
+/// assert_eq!(inv.count(), 1);
+/// assert_eq!(issues, vec![LoadIssue::UnknownItem { slot: 1, identifier: 99 }]);
+/// ```
+pub fn load<C: Container<Item> + WithCapacity>(
+    capacity: usize,
+    slots: Vec<Option<Item>>,
+    registry: &DefinitionRegistry,
+    policy: LoadPolicy,
+) -> Result<(C, Vec<LoadIssue>), LoadError> {
+    if let LoadPolicy::Placeholder(identifier) = policy {
+        if registry.get(identifier).is_none() {
+            return Err(LoadError::UnknownPlaceholder { identifier });
+        }
+    }
+
+    if slots.len() > capacity {
+        return Err(LoadError::TooManySlots {
+            slots: slots.len(),
+            capacity,
+        });
+    }
+
+    let mut container = C::with_capacity(capacity);
+    let mut issues = Vec::new();
+
+    for (slot, item) in slots.into_iter().enumerate() {
+        let item = match item {
+            Some(item) => item,
+            None => continue,
+        };
+
+        let issue = match registry.get(item.identifier()) {
+            None => Some(LoadIssue::UnknownItem {
+                slot,
+                identifier: item.identifier(),
+            }),
+            Some(definition)
+                if item.quantity() == 0 || item.quantity() > definition.max_stack() =>
+            {
+                Some(LoadIssue::InvalidQuantity {
+                    slot,
+                    quantity: item.quantity(),
+                })
+            }
+            Some(_) => None,
+        };
+
+        let item = match (issue, policy) {
+            (None, _) => item,
+            (Some(issue), LoadPolicy::Reject) => return Err(LoadError::Rejected(issue)),
+            (Some(issue), LoadPolicy::Drop) => {
+                issues.push(issue);
+                continue;
+            }
+            (Some(issue), LoadPolicy::Placeholder(identifier)) => {
+                issues.push(issue);
+                Item::new(identifier, 1)
+            }
+        };
+
+        container
+            .add_at(item, slot)
+            .map_err(|error| LoadError::Container { slot, error })?;
+    }
+
+    Ok((container, issues))
+}
+
+#[cfg(test)]
+mod loader_tests {
+    use super::{load, ContainerError, LoadError, LoadIssue, LoadPolicy};
+    use crate::collections::{ContainerView, Inventory};
+    use crate::entity::{DefinitionRegistry, Item, ItemDefinition};
+
+    fn registry() -> DefinitionRegistry {
+        let mut registry = DefinitionRegistry::new();
+        registry.register(ItemDefinition::new(1, false));
+        registry.register(ItemDefinition::new(2, true));
+        // the placeholder
+        registry.register(ItemDefinition::new(0, false));
+        registry
+    }
+
+    fn slots() -> Vec<Option<Item>> {
+        vec![
+            Some(Item::new(1, 1)),
+            None,
+            Some(Item::new(2, 500)),
+            Some(Item::new(1, 3)),
+            Some(Item::new(7, 1)),
+        ]
+    }
+
+    #[test]
+    fn load_reject() {
+        let result = load::<Inventory>(5, slots(), &registry(), LoadPolicy::Reject);
+
+        assert_eq!(
+            result.err(),
+            Some(LoadError::Rejected(LoadIssue::InvalidQuantity {
+                slot: 3,
+                quantity: 3
+            }))
+        );
+        assert_eq!(
+            load::<Inventory>(2, slots(), &registry(), LoadPolicy::Reject).err(),
+            Some(LoadError::TooManySlots {
+                slots: 5,
+                capacity: 2
+            })
+        );
+    }
+
+    #[test]
+    fn load_drop_and_placeholder() {
+        let (inv, issues) = load::<Inventory>(5, slots(), &registry(), LoadPolicy::Drop).unwrap();
+
+        assert_eq!(inv.count(), 2);
+        assert_eq!(
+            issues,
+            vec![
+                LoadIssue::InvalidQuantity {
+                    slot: 3,
+                    quantity: 3
+                },
+                LoadIssue::UnknownItem {
+                    slot: 4,
+                    identifier: 7
+                },
+            ]
+        );
+
+        let (inv, _) =
+            load::<Inventory>(5, slots(), &registry(), LoadPolicy::Placeholder(0)).unwrap();
+
+        assert_eq!(inv.count(), 4);
+        assert_eq!(inv.get_at(3), Ok(Item::new(0, 1)));
+        assert_eq!(inv.get_at(4), Ok(Item::new(0, 1)));
+
+        // a placeholder the registry does not know fails the load up front
+        let result = load::<Inventory>(5, slots(), &registry(), LoadPolicy::Placeholder(9));
+        assert_eq!(
+            result.err(),
+            Some(LoadError::UnknownPlaceholder { identifier: 9 })
+        );
+
+        // a placeholder the inventory cannot store fails the load
+        let mut registry = registry();
+        registry.register(ItemDefinition::new(usize::MAX, false));
+
+        let result = load::<Inventory>(5, slots(), &registry, LoadPolicy::Placeholder(usize::MAX));
+        assert_eq!(
+            result.err(),
+            Some(LoadError::Container {
+                slot: 3,
+                error: ContainerError::InvalidItem
+            })
+        );
+    }
+}
@@ -1,5 +1,11 @@
 mod container;
+mod event;
 mod inventory;
+mod loader;
+mod validation;
 
 pub use container::*;
+pub use event::*;
 pub use inventory::*;
+pub use loader::*;
+pub use validation::*;
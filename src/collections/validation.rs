@@ -0,0 +1,37 @@
+/// A single inconsistency found while validating a container.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// The tracked item count does not match the number of occupied slots.
+    CountMismatch { tracked: usize, occupied: usize },
+    /// A slot holds an item with a quantity of zero.
+    ZeroQuantity { slot: usize },
+    /// A slot holds more of an item than a single stack allows.
+    OverStacked { slot: usize, quantity: usize },
+}
+
+/// The outcome of validating a container.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn new() -> Self {
+        ValidationReport { issues: Vec::new() }
+    }
+
+    /// Records an issue in the report.
+    pub fn push(&mut self, issue: ValidationIssue) {
+        self.issues.push(issue);
+    }
+
+    /// Returns whether no issues were found.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns every issue found, in slot order.
+    pub fn issues(&self) -> &[ValidationIssue] {
+        &self.issues
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    /// A line is not of the form `key = value`.
+    Malformed {
+        line: usize,
+    },
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    InvalidValue {
+        line: usize,
+        key: String,
+    },
+    /// A feature name that is not in `FEATURES`.
+    UnknownFeature {
+        line: usize,
+        feature: String,
+    },
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> Self {
+        ConfigError::Io(error)
+    }
+}
+
+/// Every optional feature that can be switched on.
+pub const FEATURES: &[&str] = &["drops"];
+
+/// Settings read once at startup and consulted by the world and its
+/// systems.
+///
+/// The file format is one `key = value` pair per line, with `#` starting a
+/// comment. Keys that are left out keep their default value.
+///
+/// # Example
+/// ```
+/// # use rs_lib::config::ServerConfig;
+/// let config: ServerConfig = "max_players = 500\nfeatures = drops".parse().unwrap();
+/// assert_eq!(config.max_players, 500);
+/// assert!(config.is_enabled("drops"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerConfig {
+    pub max_players: usize,
+    pub inventory_capacity: usize,
+    /// The seed for the world's random number generator.
+    pub seed: u64,
+    /// Optional features that are switched on, by name.
+    pub features: BTreeSet<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        let features = ["drops"].iter().map(|name| name.to_string()).collect();
+
+        ServerConfig {
+            max_players: 2000,
+            inventory_capacity: 28,
+            seed: 0,
+            features,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Reads a config from a file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<ServerConfig, ConfigError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Reads a config from a file, falling back to the defaults if the file
+    /// does not exist.
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> Result<ServerConfig, ConfigError> {
+        match ServerConfig::load(path) {
+            Err(ConfigError::Io(ref error)) if error.kind() == io::ErrorKind::NotFound => {
+                Ok(ServerConfig::default())
+            }
+            result => result,
+        }
+    }
+
+    /// Returns whether an optional feature is switched on.
+    pub fn is_enabled(&self, feature: &str) -> bool {
+        self.features.contains(feature)
+    }
+}
+
+impl FromStr for ServerConfig {
+    type Err = ConfigError;
+
+    fn from_str(text: &str) -> Result<ServerConfig, ConfigError> {
+        let mut config = ServerConfig::default();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or("").trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, '=');
+            let key = parts.next().unwrap_or("").trim();
+            let value = match parts.next() {
+                Some(value) if !key.is_empty() => value.trim(),
+                _ => return Err(ConfigError::Malformed { line: line_number }),
+            };
+
+            let invalid = || ConfigError::InvalidValue {
+                line: line_number,
+                key: key.to_string(),
+            };
+
+            match key {
+                "max_players" => config.max_players = value.parse().map_err(|_| invalid())?,
+                "inventory_capacity" => {
+                    config.inventory_capacity = value.parse().map_err(|_| invalid())?
+                }
+                "seed" => config.seed = value.parse().map_err(|_| invalid())?,
+                "features" => {
+                    config.features = BTreeSet::new();
+
+                    for feature in value.split(',').map(str::trim) {
+                        if feature.is_empty() {
+                            continue;
+                        }
+                        if !FEATURES.contains(&feature) {
+                            return Err(ConfigError::UnknownFeature {
+                                line: line_number,
+                                feature: feature.to_string(),
+                            });
+                        }
+                        config.features.insert(feature.to_string());
+                    }
+                }
+                _ => {
+                    return Err(ConfigError::UnknownKey {
+                        line: line_number,
+                        key: key.to_string(),
+                    })
+                }
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod server_config_tests {
+    use super::{ConfigError, ServerConfig};
+
+    #[test]
+    fn config_parse() {
+        let text = "
+            # a small test world
+            max_players = 10
+            inventory_capacity = 30   # event rules
+            seed = 7
+            features =
+        ";
+        let config: ServerConfig = text.parse().unwrap();
+
+        assert_eq!(config.max_players, 10);
+        assert_eq!(config.inventory_capacity, 30);
+        assert_eq!(config.seed, 7);
+        assert!(!config.is_enabled("drops"));
+    }
+
+    #[test]
+    fn config_parse_errors() {
+        match "seed = 2\nmax_players".parse::<ServerConfig>() {
+            Err(ConfigError::Malformed { line }) => assert_eq!(line, 2),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        match "seed = two".parse::<ServerConfig>() {
+            Err(ConfigError::InvalidValue { line, key }) => assert_eq!((line, &*key), (1, "seed")),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        match "colour = blue".parse::<ServerConfig>() {
+            Err(ConfigError::UnknownKey { key, .. }) => assert_eq!(key, "colour"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        match "seed = 1\nfeatures = drops, trading".parse::<ServerConfig>() {
+            Err(ConfigError::UnknownFeature { line, feature }) => {
+                assert_eq!((line, &*feature), (2, "trading"))
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn config_load_missing_file() {
+        let config = ServerConfig::load_or_default("does/not/exist.conf").unwrap();
+
+        assert_eq!(config, ServerConfig::default());
+    }
+}
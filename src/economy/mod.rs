@@ -0,0 +1,3 @@
+mod wallet;
+
+pub use wallet::*;
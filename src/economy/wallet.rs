@@ -0,0 +1,146 @@
+use crate::entity::MAX_STACK;
+use std::collections::HashMap;
+
+/// The currencies a wallet can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Currency {
+    Coins,
+    Tokens,
+    Points,
+}
+
+#[derive(Debug, PartialOrd, PartialEq)]
+pub enum WalletError {
+    Overflow,
+    InsufficientFunds,
+}
+
+pub type WalletResult<T> = Result<T, WalletError>;
+
+/// Holds currency balances outside of any slot-based container. No balance
+/// can exceed `MAX_STACK`.
+#[derive(Debug, Clone, Default)]
+pub struct Wallet {
+    balances: HashMap<Currency, usize>,
+}
+
+impl Wallet {
+    pub fn new() -> Self {
+        Wallet {
+            balances: HashMap::new(),
+        }
+    }
+
+    /// Returns the balance held of a currency.
+    pub fn balance(&self, currency: Currency) -> usize {
+        self.balances.get(&currency).copied().unwrap_or(0)
+    }
+
+    /// Returns whether the wallet holds at least `amount` of a currency.
+    pub fn can_afford(&self, currency: Currency, amount: usize) -> bool {
+        self.balance(currency) >= amount
+    }
+
+    /// Adds `amount` to the balance of a currency. Fails, leaving the
+    /// balance untouched, if it would go past `MAX_STACK`.
+    ///
+    /// # Example
+    /// ```
+    /// # use rs_lib::economy::{Currency, Wallet, WalletError};
+    /// # use rs_lib::entity::MAX_STACK;
+    /// let mut wallet = Wallet::new();
+    /// assert_eq!(wallet.earn(Currency::Coins, MAX_STACK), Ok(()));
+    /// assert_eq!(wallet.earn(Currency::Coins, 1), Err(WalletError::Overflow));
+    /// ```
+    pub fn earn(&mut self, currency: Currency, amount: usize) -> WalletResult<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let balance = self
+            .balance(currency)
+            .checked_add(amount)
+            .filter(|balance| *balance <= MAX_STACK)
+            .ok_or(WalletError::Overflow)?;
+
+        self.balances.insert(currency, balance);
+        Ok(())
+    }
+
+    /// Takes `amount` from the balance of a currency.
+    pub fn spend(&mut self, currency: Currency, amount: usize) -> WalletResult<()> {
+        if !self.can_afford(currency, amount) {
+            return Err(WalletError::InsufficientFunds);
+        }
+
+        let balance = self.balance(currency) - amount;
+
+        if balance == 0 {
+            self.balances.remove(&currency);
+        } else {
+            self.balances.insert(currency, balance);
+        }
+        Ok(())
+    }
+
+    /// Moves `amount` of a currency into another wallet, e.g. when settling
+    /// a trade. Neither wallet changes if the transfer fails.
+    pub fn transfer(
+        &mut self,
+        other: &mut Wallet,
+        currency: Currency,
+        amount: usize,
+    ) -> WalletResult<()> {
+        if !self.can_afford(currency, amount) {
+            return Err(WalletError::InsufficientFunds);
+        }
+
+        other.earn(currency, amount)?;
+        self.spend(currency, amount)
+    }
+}
+
+#[cfg(test)]
+mod wallet_tests {
+    use super::{Currency, Wallet, WalletError};
+
+    #[test]
+    fn wallet_earn_spend() {
+        let mut wallet = Wallet::new();
+
+        assert_eq!(wallet.earn(Currency::Coins, 100), Ok(()));
+        assert_eq!(wallet.earn(Currency::Points, 5), Ok(()));
+        assert_eq!(wallet.spend(Currency::Coins, 40), Ok(()));
+
+        assert_eq!(wallet.balance(Currency::Coins), 60);
+        assert_eq!(wallet.balance(Currency::Points), 5);
+        assert_eq!(wallet.balance(Currency::Tokens), 0);
+
+        // earning nothing does not leave an empty balance behind
+        assert_eq!(wallet.earn(Currency::Tokens, 0), Ok(()));
+        assert!(!wallet.balances.contains_key(&Currency::Tokens));
+
+        assert_eq!(
+            wallet.spend(Currency::Tokens, 1),
+            Err(WalletError::InsufficientFunds)
+        );
+    }
+
+    #[test]
+    fn wallet_transfer() {
+        let mut buyer = Wallet::new();
+        let mut seller = Wallet::new();
+
+        let _ = buyer.earn(Currency::Coins, 10);
+
+        assert_eq!(buyer.transfer(&mut seller, Currency::Coins, 7), Ok(()));
+        assert_eq!(buyer.balance(Currency::Coins), 3);
+        assert_eq!(seller.balance(Currency::Coins), 7);
+
+        assert_eq!(
+            buyer.transfer(&mut seller, Currency::Coins, 4),
+            Err(WalletError::InsufficientFunds)
+        );
+        assert_eq!(seller.balance(Currency::Coins), 7);
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::BTreeMap;
+
+/// A value held in an attribute store.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    Bool(bool),
+    Int(i64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Attribute {
+    value: AttributeValue,
+    persistent: bool,
+    ticks_left: Option<u64>,
+}
+
+/// A per-entity store of named flags and values, such as
+/// `"teleblock_ticks"` or tutorial progress.
+///
+/// Attributes are transient unless set as persistent, and may expire after a
+/// number of ticks.
+#[derive(Debug, Clone, Default)]
+pub struct Attributes {
+    entries: BTreeMap<String, Attribute>,
+}
+
+impl Attributes {
+    pub fn new() -> Self {
+        Attributes {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Sets a transient attribute, which is never persisted.
+    pub fn set(&mut self, key: &str, value: AttributeValue) {
+        self.insert(key, value, false, None);
+    }
+
+    /// Sets an attribute that is included in `persistent()`.
+    pub fn set_persistent(&mut self, key: &str, value: AttributeValue) {
+        self.insert(key, value, true, None);
+    }
+
+    /// Sets a transient attribute that is removed after `ticks` calls to
+    /// `tick()`. With `ticks` of zero the attribute is expired already, so
+    /// it is removed instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use rs_lib::entity::{AttributeValue, Attributes};
+    /// let mut attributes = Attributes::new();
+    /// attributes.set_expiring("teleblock", AttributeValue::Bool(true), 1);
+    /// assert_eq!(attributes.get_bool("teleblock"), Some(true));
+    /// attributes.tick();
+    /// assert_eq!(attributes.get_bool("teleblock"), None);
+    /// ```
+    pub fn set_expiring(&mut self, key: &str, value: AttributeValue, ticks: u64) {
+        self.insert(key, value, false, Some(ticks));
+    }
+
+    /// Sets an attribute like `set_expiring`, but keeps it in `persistent()`
+    /// along with its remaining ticks, so its timer survives a save and load.
+    pub fn set_persistent_expiring(&mut self, key: &str, value: AttributeValue, ticks: u64) {
+        self.insert(key, value, true, Some(ticks));
+    }
+
+    fn insert(&mut self, key: &str, value: AttributeValue, persistent: bool, ticks: Option<u64>) {
+        if ticks == Some(0) {
+            self.entries.remove(key);
+            return;
+        }
+
+        let attribute = Attribute {
+            value,
+            persistent,
+            ticks_left: ticks,
+        };
+
+        self.entries.insert(key.to_string(), attribute);
+    }
+
+    /// Gets the value of an attribute.
+    pub fn get(&self, key: &str) -> Option<&AttributeValue> {
+        self.entries.get(key).map(|attribute| &attribute.value)
+    }
+
+    /// Gets an attribute if it holds a `bool`.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.get(key) {
+            Some(AttributeValue::Bool(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets an attribute if it holds an integer.
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        match self.get(key) {
+            Some(AttributeValue::Int(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets an attribute if it holds text.
+    pub fn get_text(&self, key: &str) -> Option<&str> {
+        match self.get(key) {
+            Some(AttributeValue::Text(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of ticks left before an attribute expires, or
+    /// `None` if it does not exist or never expires.
+    pub fn ticks_left(&self, key: &str) -> Option<u64> {
+        self.entries
+            .get(key)
+            .and_then(|attribute| attribute.ticks_left)
+    }
+
+    /// Removes an attribute, returning its value.
+    pub fn remove(&mut self, key: &str) -> Option<AttributeValue> {
+        self.entries.remove(key).map(|attribute| attribute.value)
+    }
+
+    /// Advances expiring attributes by one tick, removing those that run out.
+    /// Returns the keys of the removed attributes, in key order.
+    pub fn tick(&mut self) -> Vec<String> {
+        let mut expired = Vec::new();
+
+        for (key, attribute) in self.entries.iter_mut() {
+            if let Some(ticks) = attribute.ticks_left.as_mut() {
+                *ticks = ticks.saturating_sub(1);
+
+                if *ticks == 0 {
+                    expired.push(key.clone());
+                }
+            }
+        }
+
+        for key in expired.iter() {
+            self.entries.remove(key);
+        }
+
+        expired
+    }
+
+    /// Iterates over the attributes that should be saved with the entity, in
+    /// key order, along with the ticks left on those that expire.
+    pub fn persistent(&self) -> impl Iterator<Item = (&str, &AttributeValue, Option<u64>)> {
+        self.entries
+            .iter()
+            .filter(|(_, attribute)| attribute.persistent)
+            .map(|(key, attribute)| (key.as_str(), &attribute.value, attribute.ticks_left))
+    }
+}
+
+#[cfg(test)]
+mod attributes_tests {
+    use super::{AttributeValue, Attributes};
+
+    #[test]
+    fn attributes_typed_get() {
+        let mut attributes = Attributes::new();
+
+        attributes.set("running", AttributeValue::Bool(true));
+        attributes.set("tutorial_stage", AttributeValue::Int(3));
+
+        assert_eq!(attributes.get_bool("running"), Some(true));
+        assert_eq!(attributes.get_int("tutorial_stage"), Some(3));
+
+        // the wrong type reads as missing
+        assert_eq!(attributes.get_text("tutorial_stage"), None);
+        assert_eq!(attributes.get_int("missing"), None);
+    }
+
+    #[test]
+    fn attributes_expiry() {
+        let mut attributes = Attributes::new();
+
+        attributes.set_expiring("teleblock", AttributeValue::Bool(true), 2);
+        attributes.set("running", AttributeValue::Bool(true));
+
+        assert!(attributes.tick().is_empty());
+        assert_eq!(attributes.ticks_left("teleblock"), Some(1));
+        assert_eq!(attributes.tick(), vec!["teleblock".to_string()]);
+        assert_eq!(attributes.get("teleblock"), None);
+        assert_eq!(attributes.get_bool("running"), Some(true));
+
+        // zero ticks means already expired
+        attributes.set_expiring("running", AttributeValue::Bool(false), 0);
+        assert_eq!(attributes.get("running"), None);
+    }
+
+    #[test]
+    fn attributes_persistent() {
+        let mut attributes = Attributes::new();
+
+        attributes.set("running", AttributeValue::Bool(true));
+        attributes.set_persistent("tutorial_stage", AttributeValue::Int(3));
+        attributes.set_persistent_expiring("teleblock", AttributeValue::Bool(true), 5);
+        attributes.tick();
+
+        let saved = attributes.persistent().collect::<Vec<_>>();
+
+        assert_eq!(
+            saved,
+            vec![
+                ("teleblock", &AttributeValue::Bool(true), Some(4)),
+                ("tutorial_stage", &AttributeValue::Int(3), None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use super::MAX_STACK;
+use std::collections::HashMap;
+
+/// The static data describing an item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemDefinition {
+    identifier: usize,
+    stackable: bool,
+}
+
+impl ItemDefinition {
+    pub fn new(identifier: usize, stackable: bool) -> Self {
+        ItemDefinition {
+            identifier,
+            stackable,
+        }
+    }
+    pub fn identifier(&self) -> usize {
+        self.identifier
+    }
+    pub fn stackable(&self) -> bool {
+        self.stackable
+    }
+    /// Returns the largest quantity of this item a single slot can hold.
+    pub fn max_stack(&self) -> usize {
+        if self.stackable {
+            MAX_STACK
+        } else {
+            1
+        }
+    }
+}
+
+/// Holds every known item definition, keyed by identifier.
+#[derive(Debug, Clone, Default)]
+pub struct DefinitionRegistry {
+    items: HashMap<usize, ItemDefinition>,
+}
+
+impl DefinitionRegistry {
+    pub fn new() -> Self {
+        DefinitionRegistry {
+            items: HashMap::new(),
+        }
+    }
+
+    /// Registers an item definition, replacing any previous definition with
+    /// the same identifier.
+    pub fn register(&mut self, definition: ItemDefinition) {
+        self.items.insert(definition.identifier(), definition);
+    }
+
+    /// Gets the definition of an item.
+    pub fn get(&self, identifier: usize) -> Option<&ItemDefinition> {
+        self.items.get(&identifier)
+    }
+}
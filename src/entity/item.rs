@@ -1,3 +1,6 @@
+/// The largest quantity of an item a single slot can hold.
+pub const MAX_STACK: usize = 2_147_483_647;
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Item {
     identifier: usize,
@@ -7,8 +10,8 @@ pub struct Item {
 impl Item {
     pub fn new(identifier: usize, quantity: usize) -> Self {
         Item {
-            identifier: identifier,
-            quantity: quantity,
+            identifier,
+            quantity,
         }
     }
     pub fn identifier(&self) -> usize {
@@ -1,3 +1,7 @@
+mod attributes;
+mod definition;
 mod item;
 
+pub use attributes::*;
+pub use definition::*;
 pub use item::*;
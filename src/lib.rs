@@ -1,2 +1,5 @@
 pub mod collections;
+pub mod config;
+pub mod economy;
 pub mod entity;
+pub mod world;
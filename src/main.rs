@@ -1,15 +1,40 @@
-mod collections;
-mod entity;
-
-use collections::{Container, Inventory};
-use entity::Item;
+use rs_lib::collections::{Container, Inventory, WithCapacity};
+use rs_lib::config::ServerConfig;
+use rs_lib::entity::Item;
+use rs_lib::world::World;
+use std::process;
 
 fn main() {
-    let mut inv = Inventory::with_capacity(5);
+    let config = match ServerConfig::load_or_default("server.conf") {
+        Ok(config) => config,
+        Err(error) => {
+            eprintln!("failed to load server.conf: {:?}", error);
+            process::exit(1);
+        }
+    };
+
+    let mut inv = Inventory::with_capacity(config.inventory_capacity);
 
     println!("{:?}", inv);
 
     let _ = inv.add_at(Item::new(10, 1), 2);
 
     println!("{:?}", inv);
+
+    // no flush hooks are registered until there is somewhere to save to
+    let mut world = World::new(config);
+
+    match world.shutdown() {
+        Ok(report) if report.is_clean() => {}
+        Ok(report) => {
+            for failure in report.failures() {
+                eprintln!("flush hook {} failed: {}", failure.hook, failure.error);
+            }
+            process::exit(1);
+        }
+        Err(error) => {
+            eprintln!("failed to shut down: {:?}", error);
+            process::exit(1);
+        }
+    }
 }
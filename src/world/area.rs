@@ -0,0 +1,262 @@
+use super::Position;
+use std::collections::HashMap;
+
+/// The width, in tiles, of the regions areas are indexed by.
+const REGION_SIZE: i32 = 64;
+
+flags! {
+    /// Properties an area can give the tiles inside it.
+    pub struct AreaFlags {
+        WILDERNESS = 1,
+        BANK = 1 << 1,
+        MULTIWAY = 1 << 2,
+    }
+}
+
+/// The tiles an area covers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// Every tile between two corners, inclusive.
+    Rectangle { min: Position, max: Position },
+    /// Every tile on or inside a polygon, given by its vertices in order.
+    Polygon(Vec<Position>),
+}
+
+impl Shape {
+    /// Returns whether a tile lies within the shape.
+    pub fn contains(&self, position: Position) -> bool {
+        match self {
+            Shape::Rectangle { min, max } => {
+                (min.x()..=max.x()).contains(&position.x())
+                    && (min.y()..=max.y()).contains(&position.y())
+            }
+            Shape::Polygon(vertices) => polygon_contains(vertices, position),
+        }
+    }
+
+    /// Returns the corners of the smallest rectangle covering the shape.
+    fn bounds(&self) -> Option<(Position, Position)> {
+        match self {
+            Shape::Rectangle { min, max } => Some((*min, *max)),
+            Shape::Polygon(vertices) => {
+                let min_x = vertices.iter().map(Position::x).min()?;
+                let min_y = vertices.iter().map(Position::y).min()?;
+                let max_x = vertices.iter().map(Position::x).max()?;
+                let max_y = vertices.iter().map(Position::y).max()?;
+
+                Some((Position::new(min_x, min_y), Position::new(max_x, max_y)))
+            }
+        }
+    }
+}
+
+fn polygon_contains(vertices: &[Position], position: Position) -> bool {
+    if vertices.len() < 3 {
+        return false;
+    }
+
+    let (px, py) = (i64::from(position.x()), i64::from(position.y()));
+    let mut inside = false;
+
+    for (index, a) in vertices.iter().enumerate() {
+        let b = vertices[(index + 1) % vertices.len()];
+        let (ax, ay) = (i64::from(a.x()), i64::from(a.y()));
+        let (bx, by) = (i64::from(b.x()), i64::from(b.y()));
+
+        // tiles on an edge count as inside
+        let cross = (bx - ax) * (py - ay) - (by - ay) * (px - ax);
+        if cross == 0
+            && px >= ax.min(bx)
+            && px <= ax.max(bx)
+            && py >= ay.min(by)
+            && py <= ay.max(by)
+        {
+            return true;
+        }
+
+        if (ay > py) != (by > py) {
+            // the x at which the edge crosses the tile's row, compared
+            // without dividing: px < ax + (py - ay) * (bx - ax) / (by - ay)
+            let lhs = (px - ax) * (by - ay);
+            let rhs = (py - ay) * (bx - ax);
+            if (by > ay && lhs < rhs) || (by < ay && lhs > rhs) {
+                inside = !inside;
+            }
+        }
+    }
+
+    inside
+}
+
+/// A named part of the world, such as the wilderness or a bank.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Area {
+    name: String,
+    shape: Shape,
+    flags: AreaFlags,
+}
+
+impl Area {
+    pub fn new(name: &str, shape: Shape, flags: AreaFlags) -> Self {
+        Area {
+            name: name.to_string(),
+            shape,
+            flags,
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+    pub fn flags(&self) -> AreaFlags {
+        self.flags
+    }
+    /// Returns whether a tile lies within the area.
+    pub fn contains(&self, position: Position) -> bool {
+        self.shape.contains(position)
+    }
+}
+
+/// Holds every registered area, indexed by the regions they overlap so that
+/// lookups only test the areas nearby.
+#[derive(Debug, Clone, Default)]
+pub struct AreaRegistry {
+    areas: Vec<Area>,
+    regions: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl AreaRegistry {
+    pub fn new() -> Self {
+        AreaRegistry {
+            areas: Vec::new(),
+            regions: HashMap::new(),
+        }
+    }
+
+    /// Registers an area.
+    pub fn register(&mut self, area: Area) {
+        let index = self.areas.len();
+
+        if let Some((min, max)) = area.shape.bounds() {
+            let (min_x, min_y) = region_of(min);
+            let (max_x, max_y) = region_of(max);
+
+            for x in min_x..=max_x {
+                for y in min_y..=max_y {
+                    self.regions.entry((x, y)).or_default().push(index);
+                }
+            }
+        }
+
+        self.areas.push(area);
+    }
+
+    /// Gets an area by name.
+    pub fn get(&self, name: &str) -> Option<&Area> {
+        self.areas.iter().find(|area| area.name() == name)
+    }
+
+    /// Returns every area containing a tile, in registration order.
+    ///
+    /// # Example
+    /// ```
+    /// # use rs_lib::world::{Area, AreaFlags, AreaRegistry, Position, Shape};
+    /// let mut areas = AreaRegistry::new();
+    /// areas.register(Area::new(
+    ///     "wilderness",
+    ///     Shape::Rectangle { min: Position::new(0, 0), max: Position::new(99, 99) },
+    ///     AreaFlags::WILDERNESS,
+    /// ));
+    ///
+    /// assert_eq!(areas.areas_at(Position::new(50, 50)).len(), 1);
+    /// assert!(areas.areas_at(Position::new(100, 50)).is_empty());
+    /// ```
+    pub fn areas_at(&self, position: Position) -> Vec<&Area> {
+        self.regions
+            .get(&region_of(position))
+            .into_iter()
+            .flatten()
+            .map(|index| &self.areas[*index])
+            .filter(|area| area.contains(position))
+            .collect()
+    }
+
+    /// Returns the flags of every area containing a tile, combined.
+    pub fn flags_at(&self, position: Position) -> AreaFlags {
+        self.areas_at(position)
+            .iter()
+            .fold(AreaFlags::NONE, |flags, area| flags | area.flags())
+    }
+}
+
+fn region_of(position: Position) -> (i32, i32) {
+    (
+        position.x().div_euclid(REGION_SIZE),
+        position.y().div_euclid(REGION_SIZE),
+    )
+}
+
+#[cfg(test)]
+mod area_tests {
+    use super::{Area, AreaFlags, AreaRegistry, Position, Shape};
+
+    fn registry() -> AreaRegistry {
+        let mut areas = AreaRegistry::new();
+
+        areas.register(Area::new(
+            "wilderness",
+            Shape::Rectangle {
+                min: Position::new(0, 100),
+                max: Position::new(199, 299),
+            },
+            AreaFlags::WILDERNESS,
+        ));
+        // a triangle reaching into the wilderness
+        areas.register(Area::new(
+            "multi",
+            Shape::Polygon(vec![
+                Position::new(150, 150),
+                Position::new(250, 150),
+                Position::new(150, 250),
+            ]),
+            AreaFlags::MULTIWAY,
+        ));
+        areas
+    }
+
+    #[test]
+    fn area_polygon_contains() {
+        let areas = registry();
+        let multi = areas.get("multi").unwrap();
+
+        assert!(multi.contains(Position::new(150, 150)));
+        assert!(multi.contains(Position::new(200, 200)));
+        assert!(multi.contains(Position::new(160, 160)));
+        assert!(!multi.contains(Position::new(201, 201)));
+        assert!(!multi.contains(Position::new(149, 200)));
+    }
+
+    #[test]
+    fn area_areas_at() {
+        let areas = registry();
+
+        let names = |x, y| {
+            areas
+                .areas_at(Position::new(x, y))
+                .iter()
+                .map(|area| area.name())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(10, 110), vec!["wilderness"]);
+        assert_eq!(names(160, 160), vec!["wilderness", "multi"]);
+        assert_eq!(names(240, 155), vec!["multi"]);
+        assert!(names(-10, 110).is_empty());
+
+        let flags = areas.flags_at(Position::new(160, 160));
+        assert!(flags.contains(AreaFlags::WILDERNESS | AreaFlags::MULTIWAY));
+        assert!(!flags.contains(AreaFlags::BANK));
+    }
+}
@@ -0,0 +1,276 @@
+use super::Position;
+use std::collections::HashMap;
+
+flags! {
+    /// What stands on, or around the edges of, a tile.
+    pub struct CollisionFlags {
+        WALL_NORTH = 1,
+        WALL_EAST = 1 << 1,
+        WALL_SOUTH = 1 << 2,
+        WALL_WEST = 1 << 3,
+        /// A solid object fills the whole tile.
+        BLOCKED = 1 << 4,
+        /// The tile's walls and objects are low enough for projectiles to
+        /// pass over, such as fences or tables.
+        PROJECTILE_CLEAR = 1 << 5,
+    }
+}
+
+/// Holds the collision flags of every tile that has any.
+#[derive(Debug, Clone, Default)]
+pub struct CollisionMap {
+    tiles: HashMap<Position, CollisionFlags>,
+}
+
+impl CollisionMap {
+    pub fn new() -> Self {
+        CollisionMap {
+            tiles: HashMap::new(),
+        }
+    }
+
+    /// Returns the collision flags of a tile.
+    pub fn flags_at(&self, position: Position) -> CollisionFlags {
+        self.tiles
+            .get(&position)
+            .copied()
+            .unwrap_or(CollisionFlags::NONE)
+    }
+
+    /// Sets flags on a tile, keeping the ones already set.
+    pub fn add(&mut self, position: Position, flags: CollisionFlags) {
+        let flags = self.flags_at(position) | flags;
+        self.tiles.insert(position, flags);
+    }
+
+    /// Clears flags from a tile.
+    pub fn remove(&mut self, position: Position, flags: CollisionFlags) {
+        let remaining = self.flags_at(position).without(flags);
+
+        if remaining == CollisionFlags::NONE {
+            self.tiles.remove(&position);
+        } else {
+            self.tiles.insert(position, remaining);
+        }
+    }
+
+    /// Returns whether something can walk from a tile onto a neighbouring
+    /// one. Tiles that are not neighbours can never be stepped between.
+    pub fn can_step(&self, from: Position, to: Position) -> bool {
+        is_neighbour(from, to) && !self.step_blocked(from, to, None)
+    }
+
+    /// Returns whether a projectile fired from `from` can reach `to`.
+    ///
+    /// # Example
+    /// ```
+    /// # use rs_lib::world::{CollisionFlags, CollisionMap, Position};
+    /// let mut map = CollisionMap::new();
+    /// map.add(Position::new(2, 0), CollisionFlags::WALL_WEST);
+    ///
+    /// assert!(map.has_line_of_sight(Position::new(0, 0), Position::new(1, 0)));
+    /// assert!(!map.has_line_of_sight(Position::new(0, 0), Position::new(4, 0)));
+    /// ```
+    pub fn has_line_of_sight(&self, from: Position, to: Position) -> bool {
+        self.projectile_path(from, to).is_ok()
+    }
+
+    /// Traces the tiles a projectile passes through on its way from `from`
+    /// to `to`, both included.
+    ///
+    /// If the path is blocked, the last tile the projectile reaches is
+    /// returned as the error.
+    ///
+    /// The line is always drawn from the smaller of the two tiles, so a
+    /// projectile travelling the other way passes through the same tiles
+    /// and line of sight is the same in both directions.
+    pub fn projectile_path(&self, from: Position, to: Position) -> Result<Vec<Position>, Position> {
+        let path = if from <= to {
+            line(from, to)
+        } else {
+            let mut path = line(to, from);
+            path.reverse();
+            path
+        };
+
+        for step in path.windows(2) {
+            if self.step_blocked(step[0], step[1], Some((from, to))) {
+                return Err(step[0]);
+            }
+        }
+
+        Ok(path)
+    }
+
+    /// Returns whether moving between two neighbouring tiles is blocked.
+    /// A diagonal step is blocked unless both of the orthogonal routes
+    /// around its corner are clear.
+    ///
+    /// `projectile` holds the two ends of a projectile's path. Projectiles
+    /// pass over low objects, and may be fired from or at a tile holding a
+    /// solid object.
+    fn step_blocked(
+        &self,
+        from: Position,
+        to: Position,
+        projectile: Option<(Position, Position)>,
+    ) -> bool {
+        let dx = to.x() - from.x();
+        let dy = to.y() - from.y();
+
+        if dx != 0 && dy != 0 {
+            let horizontal = Position::new(to.x(), from.y());
+            let vertical = Position::new(from.x(), to.y());
+
+            return self.step_blocked(from, horizontal, projectile)
+                || self.step_blocked(horizontal, to, projectile)
+                || self.step_blocked(from, vertical, projectile)
+                || self.step_blocked(vertical, to, projectile);
+        }
+
+        let (leaving, entering) = match (dx, dy) {
+            (1, 0) => (CollisionFlags::WALL_EAST, CollisionFlags::WALL_WEST),
+            (-1, 0) => (CollisionFlags::WALL_WEST, CollisionFlags::WALL_EAST),
+            (0, 1) => (CollisionFlags::WALL_NORTH, CollisionFlags::WALL_SOUTH),
+            (0, -1) => (CollisionFlags::WALL_SOUTH, CollisionFlags::WALL_NORTH),
+            _ => return false,
+        };
+
+        let entering = match projectile {
+            Some((start, end)) if to == start || to == end => entering,
+            _ => entering | CollisionFlags::BLOCKED,
+        };
+
+        self.blocks(from, leaving, projectile.is_some())
+            || self.blocks(to, entering, projectile.is_some())
+    }
+
+    fn blocks(&self, position: Position, flags: CollisionFlags, projectile: bool) -> bool {
+        let tile = self.flags_at(position);
+
+        if projectile && tile.contains(CollisionFlags::PROJECTILE_CLEAR) {
+            return false;
+        }
+
+        tile.intersects(flags)
+    }
+}
+
+fn is_neighbour(a: Position, b: Position) -> bool {
+    let dx = (a.x() - b.x()).abs();
+    let dy = (a.y() - b.y()).abs();
+
+    dx <= 1 && dy <= 1 && (dx, dy) != (0, 0)
+}
+
+/// Lists the tiles on a straight line between two tiles, using Bresenham's
+/// algorithm.
+fn line(from: Position, to: Position) -> Vec<Position> {
+    let dx = (to.x() - from.x()).abs();
+    let dy = -(to.y() - from.y()).abs();
+    let sx = (to.x() - from.x()).signum();
+    let sy = (to.y() - from.y()).signum();
+
+    let (mut x, mut y) = (from.x(), from.y());
+    let mut error = dx + dy;
+    let mut tiles = vec![from];
+
+    while (x, y) != (to.x(), to.y()) {
+        let doubled = 2 * error;
+
+        if doubled >= dy {
+            error += dy;
+            x += sx;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += sy;
+        }
+
+        tiles.push(Position::new(x, y));
+    }
+
+    tiles
+}
+
+#[cfg(test)]
+mod collision_tests {
+    use super::{CollisionFlags, CollisionMap, Position};
+
+    #[test]
+    fn collision_can_step() {
+        let mut map = CollisionMap::new();
+
+        map.add(Position::new(1, 0), CollisionFlags::WALL_NORTH);
+        map.add(Position::new(0, 1), CollisionFlags::BLOCKED);
+
+        assert!(map.can_step(Position::new(0, 0), Position::new(1, 0)));
+        assert!(!map.can_step(Position::new(1, 0), Position::new(1, 1)));
+        assert!(!map.can_step(Position::new(0, 0), Position::new(0, 1)));
+        // both routes around the corner are blocked
+        assert!(!map.can_step(Position::new(0, 0), Position::new(1, 1)));
+        assert!(!map.can_step(Position::new(0, 0), Position::new(2, 0)));
+
+        map.remove(Position::new(0, 1), CollisionFlags::BLOCKED);
+        assert_eq!(map.flags_at(Position::new(0, 1)), CollisionFlags::NONE);
+        assert!(map.can_step(Position::new(0, 0), Position::new(0, 1)));
+    }
+
+    #[test]
+    fn collision_projectile_path() {
+        let mut map = CollisionMap::new();
+        let from = Position::new(0, 0);
+        let to = Position::new(4, 2);
+
+        assert_eq!(
+            map.projectile_path(from, to),
+            Ok(vec![
+                Position::new(0, 0),
+                Position::new(1, 1),
+                Position::new(2, 1),
+                Position::new(3, 2),
+                Position::new(4, 2),
+            ])
+        );
+
+        map.add(Position::new(3, 2), CollisionFlags::BLOCKED);
+        assert_eq!(map.projectile_path(from, to), Err(Position::new(2, 1)));
+
+        // projectiles pass over low objects, walkers do not
+        map.add(Position::new(3, 2), CollisionFlags::PROJECTILE_CLEAR);
+        assert!(map.has_line_of_sight(from, to));
+        assert!(!map.can_step(Position::new(2, 2), Position::new(3, 2)));
+    }
+
+    #[test]
+    fn collision_line_of_sight_symmetric() {
+        let mut map = CollisionMap::new();
+
+        map.add(Position::new(0, 1), CollisionFlags::BLOCKED);
+        map.add(Position::new(2, 2), CollisionFlags::WALL_NORTH);
+        map.add(Position::new(3, 1), CollisionFlags::WALL_WEST);
+        map.add(Position::new(1, 3), CollisionFlags::BLOCKED);
+        map.add(Position::new(4, 3), CollisionFlags::WALL_SOUTH);
+
+        assert_eq!(
+            map.has_line_of_sight(Position::new(0, 0), Position::new(2, 1)),
+            map.has_line_of_sight(Position::new(2, 1), Position::new(0, 0))
+        );
+
+        let tiles: Vec<Position> = (0..5)
+            .flat_map(|x| (0..5).map(move |y| Position::new(x, y)))
+            .collect();
+
+        for &a in &tiles {
+            for &b in &tiles {
+                assert_eq!(
+                    map.has_line_of_sight(a, b),
+                    map.has_line_of_sight(b, a),
+                    "{:?} and {:?}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use super::{FlushFailure, FlushHook, Rng, ShutdownReport};
+use crate::collections::{Container, ContainerView, Inventory, WithCapacity};
+use crate::config::ServerConfig;
+use crate::entity::Item;
+
+/// An input applied to the world during a tick.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldEvent {
+    Add {
+        inventory: usize,
+        item: Item,
+    },
+    Remove {
+        inventory: usize,
+        slot: usize,
+    },
+    Swap {
+        inventory: usize,
+        slot_a: usize,
+        slot_b: usize,
+    },
+    /// Rolls one item from a drop table into an inventory, using the world's
+    /// random number generator. Ignored unless the `drops` feature is on.
+    Drop {
+        inventory: usize,
+        table: Vec<Item>,
+    },
+}
+
+#[derive(Debug, PartialOrd, PartialEq)]
+pub enum WorldError {
+    /// The world has shut down and no longer accepts changes.
+    ShutDown,
+}
+
+pub type WorldResult<T> = Result<T, WorldError>;
+
+/// The game world, advanced one tick at a time.
+///
+/// Everything the world does is driven by the events it is given and its
+/// seeded random number generator, so the same seed and events always give
+/// the same state.
+#[derive(Debug)]
+pub struct World {
+    tick: u64,
+    rng: Rng,
+    config: ServerConfig,
+    inventories: Vec<Inventory>,
+    flush_hooks: Vec<FlushHook>,
+    shut_down: bool,
+}
+
+/// Cloning a world copies its state but not its flush hooks, so a copy can
+/// never run the live server's hooks. A clone of a world that has shut down
+/// is shut down too.
+impl Clone for World {
+    fn clone(&self) -> Self {
+        World {
+            tick: self.tick,
+            rng: self.rng.clone(),
+            config: self.config.clone(),
+            inventories: self.inventories.clone(),
+            flush_hooks: Vec::new(),
+            shut_down: self.shut_down,
+        }
+    }
+}
+
+impl World {
+    /// Creates a world with an empty inventory for each player slot, seeded
+    /// and sized from `config`.
+    pub fn new(config: ServerConfig) -> Self {
+        World {
+            tick: 0,
+            rng: Rng::new(config.seed),
+            inventories: (0..config.max_players)
+                .map(|_| Inventory::with_capacity(config.inventory_capacity))
+                .collect(),
+            config,
+            flush_hooks: Vec::new(),
+            shut_down: false,
+        }
+    }
+
+    /// Returns the config the world was started with.
+    pub fn config(&self) -> &ServerConfig {
+        &self.config
+    }
+
+    /// Returns the number of ticks processed so far.
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Gets an inventory by index.
+    pub fn inventory(&self, index: usize) -> Option<&Inventory> {
+        self.inventories.get(index)
+    }
+
+    /// Returns whether the world has shut down.
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down
+    }
+
+    /// Applies a tick's events in order and advances the tick, returning the
+    /// resulting state hash. Events that cannot be applied, such as adding
+    /// to a full inventory, are skipped.
+    pub fn process_tick(&mut self, events: &[WorldEvent]) -> WorldResult<u64> {
+        if self.shut_down {
+            return Err(WorldError::ShutDown);
+        }
+
+        for event in events {
+            self.apply(event);
+        }
+
+        self.tick += 1;
+        Ok(self.state_hash())
+    }
+
+    /// Registers a hook to run when the world shuts down. Hooks run in the
+    /// order they were registered.
+    pub fn add_flush_hook(&mut self, hook: FlushHook) {
+        self.flush_hooks.push(hook);
+    }
+
+    /// Shuts the world down: no more ticks are processed, then every flush
+    /// hook is run, even if an earlier one fails.
+    ///
+    /// # Example
+    /// ```
+    /// # use rs_lib::config::ServerConfig;
+    /// # use rs_lib::world::{FlushHook, World, WorldError};
+    /// let mut world = World::new(ServerConfig::default());
+    /// world.add_flush_hook(FlushHook::new("players", |_| Err("disk full".to_string())));
+    ///
+    /// let report = world.shutdown().unwrap();
+    /// assert_eq!(report.failures()[0].hook, "players");
+    /// assert_eq!(world.process_tick(&[]), Err(WorldError::ShutDown));
+    /// ```
+    pub fn shutdown(&mut self) -> WorldResult<ShutdownReport> {
+        if self.shut_down {
+            return Err(WorldError::ShutDown);
+        }
+
+        self.shut_down = true;
+
+        let mut report = ShutdownReport::default();
+
+        for hook in self.flush_hooks.iter() {
+            if let Err(error) = hook.run(self) {
+                report.push(FlushFailure {
+                    hook: hook.name().to_string(),
+                    error,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn apply(&mut self, event: &WorldEvent) {
+        match event {
+            WorldEvent::Add { inventory, item } => {
+                if let Some(inventory) = self.inventories.get_mut(*inventory) {
+                    let _ = inventory.add(item.clone());
+                }
+            }
+            WorldEvent::Remove { inventory, slot } => {
+                if let Some(inventory) = self.inventories.get_mut(*inventory) {
+                    let _ = inventory.remove_at(*slot);
+                }
+            }
+            WorldEvent::Swap {
+                inventory,
+                slot_a,
+                slot_b,
+            } => {
+                if let Some(inventory) = self.inventories.get_mut(*inventory) {
+                    let _ = inventory.swap(*slot_a, *slot_b);
+                }
+            }
+            WorldEvent::Drop { inventory, table } => {
+                if table.is_empty() || !self.config.is_enabled("drops") {
+                    return;
+                }
+
+                // roll even if the inventory is missing, so the generator
+                // advances the same way regardless
+                let item = table[self.rng.next_below(table.len())].clone();
+
+                if let Some(inventory) = self.inventories.get_mut(*inventory) {
+                    let _ = inventory.add(item);
+                }
+            }
+        }
+    }
+
+    /// Hashes the tick, the generator state and every inventory's contents.
+    /// The hash is stable across runs and platforms.
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher::new();
+
+        hasher.write(self.tick);
+        hasher.write(self.rng.state());
+
+        for inventory in self.inventories.iter() {
+            hasher.write(inventory.capacity() as u64);
+
+            for (slot, item) in inventory.iter() {
+                hasher.write(slot as u64);
+                hasher.write(item.identifier() as u64);
+                hasher.write(item.quantity() as u64);
+            }
+        }
+
+        hasher.finish()
+    }
+}
+
+/// FNV-1a, used instead of `std`'s hasher since that one may change between
+/// Rust releases.
+struct StateHasher(u64);
+
+impl StateHasher {
+    fn new() -> Self {
+        StateHasher(0xCBF2_9CE4_8422_2325)
+    }
+
+    fn write(&mut self, value: u64) {
+        for byte in value.to_le_bytes().iter() {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01B3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,18 @@
+#[macro_use]
+mod flags;
+
+mod area;
+mod collision;
+mod engine;
+mod position;
+mod replay;
+mod rng;
+mod shutdown;
+
+pub use area::*;
+pub use collision::*;
+pub use engine::*;
+pub use position::*;
+pub use replay::*;
+pub use rng::*;
+pub use shutdown::*;
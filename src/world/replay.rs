@@ -0,0 +1,205 @@
+use super::{World, WorldError, WorldEvent, WorldResult};
+
+/// The point where a replay stopped matching its recording.
+#[derive(Debug, PartialEq)]
+pub struct Desync {
+    pub tick: u64,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReplayError {
+    /// The replayed state stopped matching the recording.
+    Desync(Desync),
+    /// The world refused to process a recorded tick.
+    Refused { tick: u64, error: WorldError },
+}
+
+/// A world's starting state along with every tick's events and the state
+/// hash they produced, so a session can be played back exactly.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    start: World,
+    ticks: Vec<(Vec<WorldEvent>, u64)>,
+}
+
+impl Recording {
+    /// Starts a recording from the current state of a world. The copy kept
+    /// has none of the world's flush hooks.
+    pub fn new(world: &World) -> Self {
+        Recording {
+            start: world.clone(),
+            ticks: Vec::new(),
+        }
+    }
+
+    /// Rebuilds a recording from a starting state and the ticks recorded
+    /// after it, such as ones saved from `start` and `ticks`.
+    pub fn from_parts(start: World, ticks: Vec<(Vec<WorldEvent>, u64)>) -> Self {
+        Recording { start, ticks }
+    }
+
+    /// Returns the state the recording starts from.
+    pub fn start(&self) -> &World {
+        &self.start
+    }
+
+    /// Returns every recorded tick's events and the state hash they
+    /// produced, in order.
+    pub fn ticks(&self) -> &[(Vec<WorldEvent>, u64)] {
+        &self.ticks
+    }
+
+    /// Processes a tick on `world` and records its events and state hash.
+    pub fn record(&mut self, world: &mut World, events: Vec<WorldEvent>) -> WorldResult<u64> {
+        let hash = world.process_tick(&events)?;
+        self.ticks.push((events, hash));
+        Ok(hash)
+    }
+
+    /// Returns the number of ticks recorded.
+    pub fn len(&self) -> usize {
+        self.ticks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ticks.is_empty()
+    }
+
+    /// Plays the recording back from its starting state, checking the state
+    /// hash after every tick. Returns the final world, or the first tick
+    /// that was refused or whose state differs from the recording.
+    pub fn replay(&self) -> Result<World, ReplayError> {
+        let mut world = self.start.clone();
+
+        for (events, expected) in self.ticks.iter() {
+            let tick = world.current_tick();
+            let actual = world
+                .process_tick(events)
+                .map_err(|error| ReplayError::Refused { tick, error })?;
+
+            if actual != *expected {
+                return Err(ReplayError::Desync(Desync {
+                    tick,
+                    expected: *expected,
+                    actual,
+                }));
+            }
+        }
+
+        Ok(world)
+    }
+}
+
+#[cfg(test)]
+mod replay_tests {
+    use super::{Desync, Recording, ReplayError};
+    use crate::collections::ContainerView;
+    use crate::config::ServerConfig;
+    use crate::entity::Item;
+    use crate::world::{FlushHook, World, WorldEvent};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    fn config() -> ServerConfig {
+        ServerConfig {
+            seed: 42,
+            max_players: 2,
+            ..ServerConfig::default()
+        }
+    }
+
+    fn session(world: &mut World) -> Recording {
+        let mut recording = Recording::new(world);
+        let table = vec![Item::new(1, 1), Item::new(2, 1), Item::new(3, 1)];
+
+        for tick in 0..20 {
+            let mut events = vec![WorldEvent::Drop {
+                inventory: tick % 2,
+                table: table.clone(),
+            }];
+            if tick % 3 == 0 {
+                events.push(WorldEvent::Remove {
+                    inventory: 0,
+                    slot: 0,
+                });
+            }
+            let _ = recording.record(world, events);
+        }
+        recording
+    }
+
+    #[test]
+    fn replay_matches() {
+        let mut world = World::new(config());
+        let recording = session(&mut world);
+
+        let replayed = recording.replay().unwrap();
+
+        assert_eq!(recording.len(), 20);
+        assert_eq!(replayed.current_tick(), 20);
+        assert_eq!(replayed.state_hash(), world.state_hash());
+        assert_eq!(
+            replayed.inventory(1).unwrap().iter().collect::<Vec<_>>(),
+            world.inventory(1).unwrap().iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn replay_from_parts() {
+        let mut world = World::new(config());
+        let recording = session(&mut world);
+
+        let rebuilt = Recording::from_parts(
+            World::new(recording.start().config().clone()),
+            recording.ticks().to_vec(),
+        );
+
+        assert_eq!(rebuilt.len(), 20);
+        assert_eq!(rebuilt.replay().unwrap().state_hash(), world.state_hash());
+    }
+
+    #[test]
+    fn replay_detects_desync() {
+        let mut world = World::new(config());
+        let mut recording = session(&mut world);
+
+        // tamper with the recorded hash of the 5th tick
+        recording.ticks[4].1 ^= 1;
+
+        match recording.replay() {
+            Err(ReplayError::Desync(Desync { tick, .. })) => assert_eq!(tick, 4),
+            result => panic!("unexpected result: {:?}", result.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn replay_drops_disabled() {
+        let mut config = config();
+        config.features.clear();
+
+        let mut world = World::new(config);
+        session(&mut world);
+
+        assert_eq!(world.inventory(1).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn replay_skips_flush_hooks() {
+        let flushed = Arc::new(AtomicBool::new(false));
+        let mut world = World::new(config());
+
+        let hook_flushed = Arc::clone(&flushed);
+        world.add_flush_hook(FlushHook::new("players", move |_| {
+            hook_flushed.store(true, Ordering::SeqCst);
+            Ok(())
+        }));
+
+        let recording = session(&mut world);
+        let mut replayed = recording.replay().unwrap();
+
+        assert!(replayed.shutdown().unwrap().is_clean());
+        assert!(!flushed.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,119 @@
+use super::World;
+use std::fmt;
+use std::sync::Arc;
+
+type Flush = dyn Fn(&World) -> Result<(), String> + Send + Sync;
+
+/// A named task run when the world shuts down, such as saving every player.
+#[derive(Clone)]
+pub struct FlushHook {
+    name: String,
+    flush: Arc<Flush>,
+}
+
+impl FlushHook {
+    pub fn new<F>(name: &str, flush: F) -> Self
+    where
+        F: Fn(&World) -> Result<(), String> + Send + Sync + 'static,
+    {
+        FlushHook {
+            name: name.to_string(),
+            flush: Arc::new(flush),
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub(super) fn run(&self, world: &World) -> Result<(), String> {
+        (self.flush)(world)
+    }
+}
+
+impl fmt::Debug for FlushHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FlushHook")
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+/// A flush hook that failed during shutdown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlushFailure {
+    pub hook: String,
+    pub error: String,
+}
+
+/// The outcome of shutting down a world.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShutdownReport {
+    failures: Vec<FlushFailure>,
+}
+
+impl ShutdownReport {
+    pub(super) fn push(&mut self, failure: FlushFailure) {
+        self.failures.push(failure);
+    }
+
+    /// Returns whether every flush hook succeeded.
+    pub fn is_clean(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Returns the hooks that failed, in the order they ran.
+    pub fn failures(&self) -> &[FlushFailure] {
+        &self.failures
+    }
+}
+
+#[cfg(test)]
+mod shutdown_tests {
+    use super::{FlushFailure, FlushHook};
+    use crate::config::ServerConfig;
+    use crate::world::{World, WorldError};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn shutdown_runs_every_hook() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut world = World::new(ServerConfig::default());
+
+        for name in ["players", "ground_items", "shops"].iter() {
+            let runs = Arc::clone(&runs);
+            let name = name.to_string();
+
+            world.add_flush_hook(FlushHook::new(&name.clone(), move |_| {
+                runs.fetch_add(1, Ordering::SeqCst);
+                if name == "ground_items" {
+                    Err("disk full".to_string())
+                } else {
+                    Ok(())
+                }
+            }));
+        }
+
+        let report = world.shutdown().unwrap();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert!(!report.is_clean());
+        assert_eq!(
+            report.failures(),
+            &[FlushFailure {
+                hook: "ground_items".to_string(),
+                error: "disk full".to_string()
+            }]
+        );
+
+        // hooks only ever run once
+        assert_eq!(world.shutdown(), Err(WorldError::ShutDown));
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert!(world.is_shut_down());
+
+        // a copy of a shut down world stays shut down, without the hooks
+        let mut copy = world.clone();
+        assert!(copy.is_shut_down());
+        assert_eq!(copy.process_tick(&[]), Err(WorldError::ShutDown));
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+}